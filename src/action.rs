@@ -0,0 +1,245 @@
+use crate::input::{GamepadButton, Input};
+use crate::KeyMods;
+use rustc_hash::FxHashMap;
+use std::hash::Hash;
+use winit::{event::MouseButton, keyboard::KeyCode};
+
+/// A physical input that can be bound to an action, via [`ActionMap`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Trigger {
+    /// A physical keyboard key.
+    Key(KeyCode),
+    /// A mouse button.
+    Mouse(MouseButton),
+    /// A gamepad button, matching any connected gamepad.
+    Gamepad(GamepadButton),
+}
+
+impl Trigger {
+    fn is_pressed(&self, input: &Input) -> bool {
+        match *self {
+            Trigger::Key(code) => input.is_physical_key_pressed(code),
+            Trigger::Mouse(button) => input.is_mouse_button_pressed(button),
+            Trigger::Gamepad(button) => input
+                .gamepads()
+                .keys()
+                .any(|&id| input.is_gamepad_button_pressed(id, button)),
+        }
+    }
+
+    fn is_down(&self, input: &Input) -> bool {
+        match *self {
+            Trigger::Key(code) => input.is_physical_key_down(code),
+            Trigger::Mouse(button) => input.is_mouse_button_down(button),
+            Trigger::Gamepad(button) => input
+                .gamepads()
+                .keys()
+                .any(|&id| input.is_gamepad_button_down(id, button)),
+        }
+    }
+
+    fn is_released(&self, input: &Input) -> bool {
+        match *self {
+            Trigger::Key(code) => input.is_physical_key_released(code),
+            Trigger::Mouse(button) => input.is_mouse_button_released(button),
+            Trigger::Gamepad(button) => input
+                .gamepads()
+                .keys()
+                .any(|&id| input.is_gamepad_button_released(id, button)),
+        }
+    }
+}
+
+/// A single binding: a [`Trigger`] plus the modifiers that must be held for it to match.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Binding {
+    trigger: Trigger,
+    mods: KeyMods,
+}
+
+impl Binding {
+    /// A binding matches if every modifier set in its mask is currently held.
+    /// Modifiers it doesn't care about are ignored, no matter their state.
+    fn mods_match(&self, current: &KeyMods) -> bool {
+        (!self.mods.lshift || current.lshift)
+            && (!self.mods.rshift || current.rshift)
+            && (!self.mods.lalt || current.lalt)
+            && (!self.mods.ralt || current.ralt)
+            && (!self.mods.lcontrol || current.lcontrol)
+            && (!self.mods.rcontrol || current.rcontrol)
+            && (!self.mods.lsuper || current.lsuper)
+            && (!self.mods.rsuper || current.rsuper)
+    }
+}
+
+/// Returns `true` if any binding's mods match `current` and its trigger satisfies `triggered`.
+fn any_binding_matches(
+    bindings: &[Binding],
+    current: &KeyMods,
+    triggered: impl Fn(Trigger) -> bool,
+) -> bool {
+    bindings
+        .iter()
+        .any(|binding| binding.mods_match(current) && triggered(binding.trigger))
+}
+
+/// Resolves raw [`Trigger`]s into named actions, so game logic doesn't have to query
+/// `KeyCode`/`MouseButton`/gamepad buttons directly.
+///
+/// Multiple bindings (e.g. keyboard + mouse + gamepad) can map to the same action; any one of
+/// them matching is enough for the action to be considered pressed/down/released.
+#[derive(Debug, Default)]
+pub struct ActionMap<A: Hash + Eq> {
+    bindings: FxHashMap<A, Vec<Binding>>,
+}
+
+impl<A: Hash + Eq> ActionMap<A> {
+    /// Create an empty action map.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            bindings: FxHashMap::default(),
+        }
+    }
+
+    /// Bind a trigger to an action, requiring `mods` to be held for the binding to match.
+    /// Does not replace existing bindings for the action; use [`ActionMap::rebind`] for that.
+    #[inline]
+    pub fn bind(&mut self, action: A, trigger: Trigger, mods: KeyMods) {
+        self.bindings
+            .entry(action)
+            .or_default()
+            .push(Binding { trigger, mods });
+    }
+
+    /// Replace all bindings for an action with a single, mod-less binding to `trigger`.
+    #[inline]
+    pub fn rebind(&mut self, action: A, trigger: Trigger) {
+        self.bindings.insert(
+            action,
+            vec![Binding {
+                trigger,
+                mods: KeyMods::default(),
+            }],
+        );
+    }
+
+    /// Returns `true` if any binding for the action has just been pressed.
+    pub fn is_action_pressed(&self, input: &Input, action: &A) -> bool {
+        let mods = input.key_mods();
+
+        self.bindings.get(action).is_some_and(|bindings| {
+            any_binding_matches(bindings, &mods, |trigger| trigger.is_pressed(input))
+        })
+    }
+
+    /// Returns `true` if any binding for the action is down.
+    pub fn is_action_down(&self, input: &Input, action: &A) -> bool {
+        let mods = input.key_mods();
+
+        self.bindings.get(action).is_some_and(|bindings| {
+            any_binding_matches(bindings, &mods, |trigger| trigger.is_down(input))
+        })
+    }
+
+    /// Returns `true` if any binding for the action has just been released.
+    pub fn is_action_released(&self, input: &Input, action: &A) -> bool {
+        let mods = input.key_mods();
+
+        self.bindings.get(action).is_some_and(|bindings| {
+            any_binding_matches(bindings, &mods, |trigger| trigger.is_released(input))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{any_binding_matches, Binding, Trigger};
+    use crate::KeyMods;
+    use winit::{event::MouseButton, keyboard::KeyCode};
+
+    fn binding(trigger: Trigger, mods: KeyMods) -> Binding {
+        Binding { trigger, mods }
+    }
+
+    #[test]
+    fn mods_match_ignores_unmasked_modifiers() {
+        let binding = binding(Trigger::Key(KeyCode::KeyW), KeyMods::default());
+
+        assert!(binding.mods_match(&KeyMods::default()));
+        assert!(binding.mods_match(&KeyMods {
+            lshift: true,
+            ..Default::default()
+        }));
+    }
+
+    #[test]
+    fn mods_match_requires_every_masked_modifier_held() {
+        let mask = KeyMods {
+            lcontrol: true,
+            lshift: true,
+            ..Default::default()
+        };
+        let binding = binding(Trigger::Key(KeyCode::KeyW), mask);
+
+        assert!(!binding.mods_match(&KeyMods::default()));
+        assert!(!binding.mods_match(&KeyMods {
+            lcontrol: true,
+            ..Default::default()
+        }));
+        assert!(binding.mods_match(&KeyMods {
+            lcontrol: true,
+            lshift: true,
+            ..Default::default()
+        }));
+        // extra, unmasked modifiers held don't prevent a match
+        assert!(binding.mods_match(&KeyMods {
+            lcontrol: true,
+            lshift: true,
+            ralt: true,
+            ..Default::default()
+        }));
+    }
+
+    #[test]
+    fn any_binding_matches_ors_across_bindings() {
+        let bindings = vec![
+            binding(
+                Trigger::Key(KeyCode::KeyW),
+                KeyMods {
+                    lcontrol: true,
+                    ..Default::default()
+                },
+            ),
+            binding(Trigger::Mouse(MouseButton::Left), KeyMods::default()),
+        ];
+        let mods = KeyMods::default();
+
+        // first binding's mods don't match, but the second binding's trigger does
+        assert!(any_binding_matches(&bindings, &mods, |trigger| matches!(
+            trigger,
+            Trigger::Mouse(MouseButton::Left)
+        )));
+
+        // neither binding's trigger matches
+        assert!(!any_binding_matches(&bindings, &mods, |trigger| matches!(
+            trigger,
+            Trigger::Mouse(MouseButton::Right)
+        )));
+    }
+
+    #[test]
+    fn any_binding_matches_respects_mods_even_when_trigger_matches() {
+        let bindings = vec![binding(
+            Trigger::Key(KeyCode::KeyW),
+            KeyMods {
+                lcontrol: true,
+                ..Default::default()
+            },
+        )];
+
+        assert!(!any_binding_matches(&bindings, &KeyMods::default(), |_| {
+            true
+        }));
+    }
+}
@@ -147,13 +147,16 @@ impl<A: App<D>, D> ApplicationHandler for AppHandler<A, D> {
         }
     }
 
-    #[inline]
     fn device_event(
         &mut self,
         event_loop: &ActiveEventLoop,
         device_id: winit::event::DeviceId,
         event: winit::event::DeviceEvent,
     ) {
+        if let Some((_, ctx)) = self.context.get() {
+            ctx.input.process_device_event(&event);
+        }
+
         let _ = self.pass_event(Event::DeviceEvent { device_id, event }, event_loop);
     }
 
@@ -172,6 +175,8 @@ impl<A: App<D>, D> ApplicationHandler for AppHandler<A, D> {
 
             self.accumulated_time += elapsed;
 
+            ctx.input.poll_gamepads();
+
             let mut keys_updated = false;
 
             while self.accumulated_time > ctx.target_frame_time {
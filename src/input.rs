@@ -1,12 +1,24 @@
+use gilrs::Gilrs;
 use rustc_hash::FxHashMap;
 use std::sync::Arc;
+use web_time::Duration;
 use winit::{
     dpi::PhysicalPosition,
-    event::{ElementState, Modifiers, MouseButton, MouseScrollDelta, WindowEvent},
+    event::{
+        DeviceEvent, ElementState, Ime, KeyEvent, Modifiers, MouseButton, MouseScrollDelta,
+        TouchPhase, WindowEvent,
+    },
     keyboard::{Key, KeyCode, ModifiersKeyState, NamedKey, PhysicalKey},
     window::Window,
 };
 
+/// A gamepad analog axis, as reported by the underlying [`gilrs`] backend.
+pub use gilrs::Axis as GamepadAxis;
+/// A gamepad button, as reported by the underlying [`gilrs`] backend.
+pub use gilrs::Button as GamepadButton;
+/// A gamepad id, as reported by the underlying [`gilrs`] backend.
+pub use gilrs::GamepadId;
+
 /// Keyboard modifiers.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
 pub struct KeyMods {
@@ -84,22 +96,127 @@ impl From<ElementState> for InputState {
     }
 }
 
+/// Deadzones applied to gamepad analog sticks and triggers.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GamepadDeadzone {
+    /// Radial deadzone applied to analog sticks, in the `0.0..=1.0` range.
+    pub stick: f32,
+    /// Linear deadzone applied to analog triggers, in the `0.0..=1.0` range.
+    pub trigger: f32,
+}
+
+impl Default for GamepadDeadzone {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            stick: 0.15,
+            trigger: 0.05,
+        }
+    }
+}
+
+/// Applies a radial deadzone to a stick vector so diagonal input isn't clipped.
+fn apply_stick_deadzone(x: f32, y: f32, deadzone: f32) -> (f32, f32) {
+    let magnitude = (x * x + y * y).sqrt();
+
+    if magnitude < deadzone || magnitude == 0. {
+        (0., 0.)
+    } else {
+        let scale = (magnitude - deadzone) / (1. - deadzone) / magnitude;
+        (x * scale, y * scale)
+    }
+}
+
+/// Applies a linear deadzone to a trigger value.
+fn apply_trigger_deadzone(value: f32, deadzone: f32) -> f32 {
+    if value < deadzone {
+        0.
+    } else {
+        (value - deadzone) / (1. - deadzone)
+    }
+}
+
+/// Per-gamepad input state.
+#[derive(Clone, Debug, Default)]
+pub struct GamepadState {
+    buttons: FxHashMap<GamepadButton, InputState>,
+    left_stick: (f32, f32),
+    right_stick: (f32, f32),
+    left_trigger: f32,
+    right_trigger: f32,
+}
+
+impl GamepadState {
+    /// Left stick position after the radial deadzone has been applied.
+    #[inline]
+    pub fn left_stick(&self) -> (f32, f32) {
+        self.left_stick
+    }
+
+    /// Right stick position after the radial deadzone has been applied.
+    #[inline]
+    pub fn right_stick(&self) -> (f32, f32) {
+        self.right_stick
+    }
+
+    /// Left trigger value after the linear deadzone has been applied.
+    #[inline]
+    pub fn left_trigger(&self) -> f32 {
+        self.left_trigger
+    }
+
+    /// Right trigger value after the linear deadzone has been applied.
+    #[inline]
+    pub fn right_trigger(&self) -> f32 {
+        self.right_trigger
+    }
+}
+
+/// State of a single finger touching the screen.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Touch {
+    /// Id of the finger, stable for as long as it stays on the screen.
+    pub id: u64,
+    /// Current position of the touch.
+    pub position: PhysicalPosition<f64>,
+    /// Input state of the touch, derived from winit's [`TouchPhase`].
+    pub state: InputState,
+}
+
 /// Input handler.
 #[derive(Debug)]
 pub struct Input {
     pub(crate) window: Arc<Window>,
     mods: KeyMods,
     physical_keys: FxHashMap<KeyCode, InputState>,
-    logical_keys: FxHashMap<NamedKey, InputState>,
+    logical_keys: FxHashMap<Key, InputState>,
     mouse_buttons: FxHashMap<MouseButton, InputState>,
     cursor_pos: PhysicalPosition<f64>,
     mouse_scroll: MouseScrollDelta,
+    mouse_delta: (f64, f64),
+    text: String,
+    preedit: Option<String>,
+    touches: FxHashMap<u64, Touch>,
+    gilrs: Gilrs,
+    gamepads: FxHashMap<GamepadId, GamepadState>,
+    gamepad_deadzone: GamepadDeadzone,
 }
 
 impl Input {
-    #[inline]
-    pub(crate) fn new(window: Arc<Window>) -> Self {
-        Self {
+    pub(crate) fn new(window: Arc<Window>) -> anyhow::Result<Self> {
+        // `NotImplemented` carries a usable dummy `Gilrs` for platforms without a gamepad
+        // backend (e.g. no udev at runtime) - apps that never touch gamepads shouldn't fail
+        // to start just because of that.
+        let gilrs = match Gilrs::new() {
+            Ok(gilrs) => gilrs,
+            Err(gilrs::Error::NotImplemented(gilrs)) => {
+                log::warn!("gamepad backend not available, gamepad input will be disabled");
+                gilrs
+            }
+            Err(err) => return Err(anyhow::anyhow!(err.to_string())),
+        };
+
+        Ok(Self {
             window,
             mods: KeyMods::default(),
             physical_keys: FxHashMap::default(),
@@ -107,7 +224,14 @@ impl Input {
             mouse_buttons: FxHashMap::default(),
             cursor_pos: PhysicalPosition::new(0., 0.),
             mouse_scroll: MouseScrollDelta::LineDelta(0., 0.),
-        }
+            mouse_delta: (0., 0.),
+            text: String::new(),
+            preedit: None,
+            touches: FxHashMap::default(),
+            gilrs,
+            gamepads: FxHashMap::default(),
+            gamepad_deadzone: GamepadDeadzone::default(),
+        })
     }
 
     /// Cursor position (from [`WindowEvent::CursorMoved`](https://docs.rs/winit/latest/winit/event/enum.WindowEvent.html#variant.CursorMoved)).
@@ -122,6 +246,43 @@ impl Input {
         self.mouse_scroll
     }
 
+    /// Relative mouse motion accumulated since the last update
+    /// (from [`DeviceEvent::MouseMotion`](https://docs.rs/winit/latest/winit/event/enum.DeviceEvent.html#variant.MouseMotion)).
+    ///
+    /// Unlike [`Input::cursor_pos`] this keeps reporting motion even while the cursor is grabbed,
+    /// which makes it suitable for FPS-style camera controls.
+    #[inline]
+    pub fn mouse_delta(&self) -> (f64, f64) {
+        self.mouse_delta
+    }
+
+    /// Text typed since the last update, including characters committed by an IME.
+    #[inline]
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Current IME preedit (composition) text, if an IME composition is in progress.
+    #[inline]
+    pub fn preedit(&self) -> Option<&str> {
+        self.preedit.as_deref()
+    }
+
+    /// Currently tracked touches, keyed by finger id.
+    #[inline]
+    pub fn touches(&self) -> &FxHashMap<u64, Touch> {
+        &self.touches
+    }
+
+    /// Number of fingers currently touching the screen.
+    #[inline]
+    pub fn active_touch_count(&self) -> usize {
+        self.touches
+            .values()
+            .filter(|touch| touch.state.is_any_down())
+            .count()
+    }
+
     /// Get current keyboard modifiers.
     #[inline]
     pub fn key_mods(&self) -> KeyMods {
@@ -158,9 +319,10 @@ impl Input {
             .map_or(false, InputState::is_released)
     }
 
-    /// All input states of logical keys.
+    /// All input states of logical keys, keyed on the full [`Key`], including [`Key::Character`]
+    /// variants for layout-aware character keys.
     #[inline]
-    pub fn logical_keys(&self) -> &FxHashMap<NamedKey, InputState> {
+    pub fn logical_keys(&self) -> &FxHashMap<Key, InputState> {
         &self.logical_keys
     }
 
@@ -168,7 +330,7 @@ impl Input {
     #[inline]
     pub fn is_logical_key_pressed(&self, key: NamedKey) -> bool {
         self.logical_keys
-            .get(&key)
+            .get(&Key::Named(key))
             .map_or(false, InputState::is_pressed)
     }
 
@@ -176,7 +338,7 @@ impl Input {
     #[inline]
     pub fn is_logical_key_down(&self, key: NamedKey) -> bool {
         self.logical_keys
-            .get(&key)
+            .get(&Key::Named(key))
             .map_or(false, InputState::is_any_down)
     }
 
@@ -184,7 +346,35 @@ impl Input {
     #[inline]
     pub fn is_logical_key_released(&self, key: NamedKey) -> bool {
         self.logical_keys
-            .get(&key)
+            .get(&Key::Named(key))
+            .map_or(false, InputState::is_released)
+    }
+
+    /// Returns `true` if a layout-aware character key has just been pressed.
+    ///
+    /// Unlike [`Input::is_physical_key_pressed`], this follows the user's keyboard layout, so
+    /// e.g. checking `"w"` matches the key labelled W on AZERTY, not the key in the QWERTY W
+    /// position (which types `"z"` there).
+    #[inline]
+    pub fn is_logical_char_pressed(&self, c: &str) -> bool {
+        self.logical_keys
+            .get(&Key::Character(c.into()))
+            .map_or(false, InputState::is_pressed)
+    }
+
+    /// Returns `true` if a layout-aware character key is down.
+    #[inline]
+    pub fn is_logical_char_down(&self, c: &str) -> bool {
+        self.logical_keys
+            .get(&Key::Character(c.into()))
+            .map_or(false, InputState::is_any_down)
+    }
+
+    /// Returns `true` if a layout-aware character key has just been released.
+    #[inline]
+    pub fn is_logical_char_released(&self, c: &str) -> bool {
+        self.logical_keys
+            .get(&Key::Character(c.into()))
             .map_or(false, InputState::is_released)
     }
 
@@ -218,6 +408,154 @@ impl Input {
             .map_or(false, InputState::is_released)
     }
 
+    /// Deadzones applied to gamepad analog sticks and triggers.
+    #[inline]
+    pub fn gamepad_deadzone(&self) -> GamepadDeadzone {
+        self.gamepad_deadzone
+    }
+
+    /// Set the deadzones applied to gamepad analog sticks and triggers.
+    #[inline]
+    pub fn set_gamepad_deadzone(&mut self, deadzone: GamepadDeadzone) {
+        self.gamepad_deadzone = deadzone;
+    }
+
+    /// Currently connected gamepads.
+    #[inline]
+    pub fn gamepads(&self) -> &FxHashMap<GamepadId, GamepadState> {
+        &self.gamepads
+    }
+
+    /// Returns `true` if the gamepad is currently connected.
+    #[inline]
+    pub fn is_gamepad_connected(&self, id: GamepadId) -> bool {
+        self.gamepads.contains_key(&id)
+    }
+
+    /// Returns `true` if a gamepad button has just been pressed.
+    #[inline]
+    pub fn is_gamepad_button_pressed(&self, id: GamepadId, button: GamepadButton) -> bool {
+        self.gamepads
+            .get(&id)
+            .and_then(|gamepad| gamepad.buttons.get(&button))
+            .map_or(false, InputState::is_pressed)
+    }
+
+    /// Returns `true` if a gamepad button is down.
+    #[inline]
+    pub fn is_gamepad_button_down(&self, id: GamepadId, button: GamepadButton) -> bool {
+        self.gamepads
+            .get(&id)
+            .and_then(|gamepad| gamepad.buttons.get(&button))
+            .map_or(false, InputState::is_any_down)
+    }
+
+    /// Returns `true` if a gamepad button has just been released.
+    #[inline]
+    pub fn is_gamepad_button_released(&self, id: GamepadId, button: GamepadButton) -> bool {
+        self.gamepads
+            .get(&id)
+            .and_then(|gamepad| gamepad.buttons.get(&button))
+            .map_or(false, InputState::is_released)
+    }
+
+    /// Set the rumble (force feedback) motors of a connected gamepad.
+    pub fn set_rumble(
+        &mut self,
+        id: GamepadId,
+        strong: f32,
+        weak: f32,
+        duration: Duration,
+    ) -> anyhow::Result<()> {
+        use gilrs::ff::{BaseEffect, BaseEffectType, EffectBuilder, Replay, Ticks};
+
+        let scheduling = Replay {
+            after: Ticks::from_ms(0),
+            play_for: Ticks::from_ms(duration.as_millis() as u32),
+            with_delay: Ticks::from_ms(0),
+        };
+
+        let effect = EffectBuilder::new()
+            .add_effect(BaseEffect {
+                kind: BaseEffectType::Strong {
+                    magnitude: (strong.clamp(0., 1.) * u16::MAX as f32) as u16,
+                },
+                scheduling,
+                ..Default::default()
+            })
+            .add_effect(BaseEffect {
+                kind: BaseEffectType::Weak {
+                    magnitude: (weak.clamp(0., 1.) * u16::MAX as f32) as u16,
+                },
+                scheduling,
+                ..Default::default()
+            })
+            .gamepads(&[id])
+            .finish(&mut self.gilrs)
+            .map_err(|err| anyhow::anyhow!(err.to_string()))?;
+
+        effect
+            .play()
+            .map_err(|err| anyhow::anyhow!(err.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Polls the gamepad backend for new events.
+    ///
+    /// Should be called once per `about_to_wait`, before the fixed-update loop,
+    /// so that button edges line up with [`Input::update_keys`].
+    pub(crate) fn poll_gamepads(&mut self) {
+        while let Some(gilrs::Event { id, event, .. }) = self.gilrs.next_event() {
+            match event {
+                gilrs::EventType::Connected => {
+                    self.gamepads.insert(id, GamepadState::default());
+                }
+                gilrs::EventType::Disconnected => {
+                    self.gamepads.remove(&id);
+                }
+                gilrs::EventType::ButtonPressed(button, _) => {
+                    self.gamepads
+                        .entry(id)
+                        .or_default()
+                        .buttons
+                        .insert(button, InputState::Pressed);
+                }
+                gilrs::EventType::ButtonReleased(button, _) => {
+                    self.gamepads
+                        .entry(id)
+                        .or_default()
+                        .buttons
+                        .insert(button, InputState::Released);
+                }
+                _ => {}
+            }
+        }
+
+        let deadzone = self.gamepad_deadzone;
+
+        for (id, gamepad) in self.gamepads.iter_mut() {
+            let Some(pad) = self.gilrs.connected_gamepad(*id) else {
+                continue;
+            };
+
+            gamepad.left_stick = apply_stick_deadzone(
+                pad.value(GamepadAxis::LeftStickX),
+                pad.value(GamepadAxis::LeftStickY),
+                deadzone.stick,
+            );
+            gamepad.right_stick = apply_stick_deadzone(
+                pad.value(GamepadAxis::RightStickX),
+                pad.value(GamepadAxis::RightStickY),
+                deadzone.stick,
+            );
+            gamepad.left_trigger =
+                apply_trigger_deadzone(pad.value(GamepadAxis::LeftZ), deadzone.trigger);
+            gamepad.right_trigger =
+                apply_trigger_deadzone(pad.value(GamepadAxis::RightZ), deadzone.trigger);
+        }
+    }
+
     pub(crate) fn update_keys(&mut self) {
         self.physical_keys.retain(|_, state| match state {
             InputState::Pressed => {
@@ -246,7 +584,44 @@ impl Input {
             InputState::Released => false,
         });
 
+        for gamepad in self.gamepads.values_mut() {
+            gamepad.buttons.retain(|_, state| match state {
+                InputState::Pressed => {
+                    *state = InputState::Down;
+                    true
+                }
+                InputState::Down => true,
+                InputState::Released => false,
+            });
+        }
+
+        self.touches.retain(|_, touch| match touch.state {
+            InputState::Pressed => {
+                touch.state = InputState::Down;
+                true
+            }
+            InputState::Down => true,
+            InputState::Released => false,
+        });
+
         self.mouse_scroll = MouseScrollDelta::LineDelta(0., 0.);
+        self.mouse_delta = (0., 0.);
+        self.text.clear();
+    }
+
+    fn push_text(&mut self, event: &KeyEvent) {
+        if event.state == ElementState::Pressed {
+            if let Key::Character(s) = &event.logical_key {
+                self.text.push_str(s);
+            }
+        }
+    }
+
+    pub(crate) fn process_device_event(&mut self, event: &DeviceEvent) {
+        if let DeviceEvent::MouseMotion { delta } = event {
+            self.mouse_delta.0 += delta.0;
+            self.mouse_delta.1 += delta.1;
+        }
     }
 
     pub(crate) fn process_event(&mut self, event: &WindowEvent) {
@@ -260,13 +635,33 @@ impl Input {
                     self.physical_keys.insert(key_code, event.state.into());
                 }
 
-                if let Key::Named(key) = event.logical_key {
-                    self.logical_keys.insert(key, event.state.into());
-                }
+                self.logical_keys
+                    .insert(event.logical_key.clone(), event.state.into());
+
+                self.push_text(event);
+            }
+            // repeated key events don't update press/release state, but still produce text
+            WindowEvent::KeyboardInput {
+                device_id: _,
+                event,
+                is_synthetic: false,
+            } => {
+                self.push_text(event);
             }
             WindowEvent::ModifiersChanged(mods) => {
                 self.mods.update(mods);
             }
+            WindowEvent::Ime(ime) => match ime {
+                Ime::Commit(text) => self.text.push_str(text),
+                Ime::Preedit(text, _) => {
+                    self.preedit = if text.is_empty() {
+                        None
+                    } else {
+                        Some(text.clone())
+                    };
+                }
+                Ime::Enabled | Ime::Disabled => self.preedit = None,
+            },
             WindowEvent::CursorMoved {
                 device_id: _,
                 position,
@@ -289,7 +684,76 @@ impl Input {
             } => {
                 self.mouse_buttons.insert(*button, (*state).into());
             }
+            WindowEvent::Touch(touch) => match touch.phase {
+                TouchPhase::Started => {
+                    self.touches.insert(
+                        touch.id,
+                        Touch {
+                            id: touch.id,
+                            position: touch.location,
+                            state: InputState::Pressed,
+                        },
+                    );
+                }
+                TouchPhase::Moved => {
+                    if let Some(existing) = self.touches.get_mut(&touch.id) {
+                        existing.position = touch.location;
+                    }
+                }
+                TouchPhase::Ended | TouchPhase::Cancelled => {
+                    if let Some(existing) = self.touches.get_mut(&touch.id) {
+                        existing.position = touch.location;
+                        existing.state = InputState::Released;
+                    }
+                }
+            },
             _ => {}
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{apply_stick_deadzone, apply_trigger_deadzone};
+
+    #[test]
+    fn stick_deadzone_zeroes_zero_vector() {
+        assert_eq!(apply_stick_deadzone(0., 0., 0.2), (0., 0.));
+    }
+
+    #[test]
+    fn stick_deadzone_clips_below_threshold() {
+        assert_eq!(apply_stick_deadzone(0.1, 0., 0.2), (0., 0.));
+        assert_eq!(apply_stick_deadzone(0.1, 0.1, 0.2), (0., 0.));
+    }
+
+    #[test]
+    fn stick_deadzone_rescales_above_threshold() {
+        let (x, y) = apply_stick_deadzone(0.6, 0., 0.2);
+        assert!((x - 0.5).abs() < 1e-6);
+        assert_eq!(y, 0.);
+    }
+
+    #[test]
+    fn stick_deadzone_preserves_unit_magnitude() {
+        let (x, y) = apply_stick_deadzone(1., 0., 0.2);
+        assert!((x - 1.).abs() < 1e-6);
+        assert_eq!(y, 0.);
+    }
+
+    #[test]
+    fn trigger_deadzone_zeroes_below_threshold() {
+        assert_eq!(apply_trigger_deadzone(0.03, 0.05), 0.);
+    }
+
+    #[test]
+    fn trigger_deadzone_rescales_above_threshold() {
+        let value = apply_trigger_deadzone(0.55, 0.05);
+        assert!((value - 0.5263158).abs() < 1e-6);
+    }
+
+    #[test]
+    fn trigger_deadzone_preserves_unit_magnitude() {
+        assert!((apply_trigger_deadzone(1., 0.05) - 1.).abs() < 1e-6);
+    }
+}
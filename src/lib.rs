@@ -5,13 +5,20 @@ use cfg_if::cfg_if;
 use handler::AppHandler;
 use web_time::Duration;
 use winit::{
+    dpi::{Position, Size},
     event::Event,
     event_loop::{ActiveEventLoop, ControlFlow, EventLoop},
+    window::{CursorGrabMode, CursorIcon},
 };
 
+mod action;
 mod handler;
 mod input;
-pub use input::{Input, InputState};
+pub use action::{ActionMap, Trigger};
+pub use input::{
+    GamepadAxis, GamepadButton, GamepadDeadzone, GamepadId, GamepadState, Input, InputState,
+    KeyMods,
+};
 
 pub use anyhow;
 pub use winit;
@@ -72,6 +79,66 @@ impl Context {
     pub fn exit(&mut self) {
         self.exit = true;
     }
+
+    /// Set the rumble (force feedback) motors of a connected gamepad.
+    #[inline]
+    pub fn set_rumble(
+        &mut self,
+        id: GamepadId,
+        strong: f32,
+        weak: f32,
+        duration: Duration,
+    ) -> anyhow::Result<()> {
+        self.input.set_rumble(id, strong, weak, duration)
+    }
+
+    /// Enable or disable IME (input method editor) composition for the window.
+    ///
+    /// Only enable this while a text field is focused; IME popups are distracting otherwise.
+    #[inline]
+    pub fn set_ime_allowed(&self, allowed: bool) {
+        self.input.window.set_ime_allowed(allowed);
+    }
+
+    /// Set the area of the window (usually the focused text field) the IME candidate box should
+    /// avoid, in window coordinates.
+    #[inline]
+    pub fn set_ime_cursor_area(&self, position: impl Into<Position>, size: impl Into<Size>) {
+        self.input.window.set_ime_cursor_area(position, size);
+    }
+
+    /// Set the cursor icon shown over the window.
+    #[inline]
+    pub fn set_cursor_icon(&self, icon: CursorIcon) {
+        self.input.window.set_cursor(icon);
+    }
+
+    /// Show or hide the cursor while it's over the window.
+    #[inline]
+    pub fn set_cursor_visible(&self, visible: bool) {
+        self.input.window.set_cursor_visible(visible);
+    }
+
+    /// Set the cursor grab mode. Relative motion can be read from [`Input::mouse_delta`].
+    ///
+    /// For [`CursorGrabMode::Locked`], falls back to [`CursorGrabMode::Confined`] if the platform
+    /// rejects locking; other modes are passed to the window as they are. This does not hide the
+    /// cursor; use [`Context::set_cursor_visible`] for that.
+    pub fn set_cursor_grab(&self, grab: CursorGrabMode) -> anyhow::Result<()> {
+        match grab {
+            CursorGrabMode::Locked => self
+                .input
+                .window
+                .set_cursor_grab(CursorGrabMode::Locked)
+                .or_else(|_| self.input.window.set_cursor_grab(CursorGrabMode::Confined))
+                .map_err(|err| anyhow::anyhow!(err)),
+            grab => self
+                .input
+                .window
+                .set_cursor_grab(grab)
+                .map_err(|err| anyhow::anyhow!(err)),
+        }
+    }
 }
 
 /// Application trait.